@@ -1,29 +1,147 @@
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{Device, FromSample, SampleFormat, SizedSample, StreamConfig};
+use cpal::{
+    Device, FromSample, SampleFormat, SizedSample, StreamConfig, SupportedStreamConfig,
+};
 use fundsp::hacker::{
-    hammond_hz, multipass, reverb_stereo, sine, sine_hz, soft_saw_hz, square_hz, wave64, Wave64,
+    hammond_hz, multipass, reverb_stereo, shared, sine, sine_hz, soft_saw_hz, square_hz, var,
+    wave64, Shared, Wave64,
 };
 use fundsp::prelude::AudioUnit64;
-use std::sync::Arc;
+use std::cell::UnsafeCell;
+use std::fs::File;
+use std::io::BufWriter;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Number of slots in the ring buffer. One slot always stays empty to tell a
+/// full buffer from an empty one, so it holds up to `RING_CAPACITY - 1` stereo
+/// frames. Large enough to ride out a slow block from a heavy graph without the
+/// audio callback starving.
+const RING_CAPACITY: usize = 8192;
+
+/// A fixed-size, lock-free single-producer/single-consumer ring buffer.
+///
+/// Audio generation runs on a producer thread that calls [`insert`], while the
+/// real-time cpal callback calls [`drain`]. The separate write/read indices and
+/// atomic ordering mean neither side ever has to take a lock, so the callback
+/// can never block on `AudioUnit64` computation.
+///
+/// [`insert`]: CircularBuffer::insert
+/// [`drain`]: CircularBuffer::drain
+struct CircularBuffer<T> {
+    buffer: Vec<UnsafeCell<T>>,
+    capacity: usize,
+    write_index: AtomicUsize,
+    read_index: AtomicUsize,
+    /// Count of frames the consumer had to replace with silence.
+    underruns: AtomicUsize,
+}
+
+// Safe because there is exactly one producer and one consumer: `insert` only
+// touches the write index and `drain` only touches the read index.
+unsafe impl<T: Send> Send for CircularBuffer<T> {}
+unsafe impl<T: Send> Sync for CircularBuffer<T> {}
+
+impl<T: Copy + Default> CircularBuffer<T> {
+    fn new(capacity: usize) -> Self {
+        let mut buffer = Vec::with_capacity(capacity);
+        buffer.resize_with(capacity, || UnsafeCell::new(T::default()));
+        CircularBuffer {
+            buffer,
+            capacity,
+            write_index: AtomicUsize::new(0),
+            read_index: AtomicUsize::new(0),
+            underruns: AtomicUsize::new(0),
+        }
+    }
+
+    /// Push a frame onto the buffer. Returns `false` — dropping the frame — if
+    /// the buffer is full, so the producer never overwrites unread data.
+    fn insert(&self, value: T) -> bool {
+        let write = self.write_index.load(Ordering::Relaxed);
+        let next = (write + 1) % self.capacity;
+        if next == self.read_index.load(Ordering::Acquire) {
+            return false;
+        }
+        // Only this (producer) thread writes to this slot.
+        unsafe { *self.buffer[write].get() = value };
+        self.write_index.store(next, Ordering::Release);
+        true
+    }
+
+    /// Pop a frame. On an empty buffer this returns silence (`T::default()`) and
+    /// counts the underrun rather than blocking the audio callback.
+    fn drain(&self) -> T {
+        let read = self.read_index.load(Ordering::Relaxed);
+        if read == self.write_index.load(Ordering::Acquire) {
+            self.underruns.fetch_add(1, Ordering::Relaxed);
+            return T::default();
+        }
+        let value = unsafe { *self.buffer[read].get() };
+        self.read_index
+            .store((read + 1) % self.capacity, Ordering::Release);
+        value
+    }
+
+    /// Total number of frames the consumer has had to replace with silence so
+    /// far. Used by the producer to report dropouts.
+    fn underruns(&self) -> usize {
+        self.underruns.load(Ordering::Relaxed)
+    }
+}
+
+/// A WAV writer plus the information needed to feed it samples in the right
+/// format. `is_float` mirrors the `WavSpec`'s sample format so we can convert
+/// each `f64` frame to the `f32`/`i16` the file expects — independently of the
+/// cpal device sample type.
+struct Recorder {
+    writer: hound::WavWriter<BufWriter<File>>,
+    is_float: bool,
+}
+
+/// Shared handle to the recorder. The producer thread and the main thread both
+/// hold a clone; the `Option` lets us `take()` the writer out to `finalize()` it
+/// once recording is done.
+type RecordHandle = Arc<Mutex<Option<Recorder>>>;
+
+impl Recorder {
+    /// Write one stereo frame, converting to the file's sample format. Called
+    /// from the producer thread, never from the real-time audio callback.
+    fn write_frame(&mut self, left: f64, right: f64) {
+        // Fill every channel the spec declares, as `write_data` does for the
+        // device: even channels get the left sample, odd channels the right.
+        let channels = self.writer.spec().channels;
+        for channel in 0..channels {
+            let sample = if channel & 1 == 0 { left } else { right };
+            if self.is_float {
+                let _ = self.writer.write_sample(sample as f32);
+            } else {
+                let _ = self.writer.write_sample(f64_to_i16(sample));
+            }
+        }
+    }
+}
+
+/// Convert a `[-1.0, 1.0]` sample to a 16-bit integer sample, clamping to avoid
+/// wrap-around on overshoot.
+fn f64_to_i16(sample: f64) -> i16 {
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f64) as i16
+}
 
 /// This is the main function that is the entry point when we launch the
 /// binary, either directly or with `cargo run`.
 fn main() {
-    // Change the `create_sine_440` function to any of the functions
-    // that create a `Box<dyn AudioUnit64>` below, to change the
-    // sound that's generated.
-    let audio_graph = create_sine_440();
-
-    // This function starts the thread that creates the audio and sends
-    // it to CPAL so that we can hear it.
-    run_output(audio_graph);
-
-    // The audio is being played on a thread, and will run infinitely.
-    // As soon as the main function exits, the sound will stop, so we
-    // can sleep the main thread for a while so we can hear it.
-    // Change the duration to play the sound for more or less time.
-    let duration = 5;
-    std::thread::sleep(std::time::Duration::from_secs(duration));
+    // Play the interactive keyboard instrument. The key loop keeps the
+    // programme (and the audio thread) alive, so there is no `sleep` here.
+    //
+    // To play a fixed graph instead, build one with any of the `create_*`
+    // functions below and hand it to `run_output`, then sleep the main thread
+    // for as long as you want to hear it, e.g.
+    //
+    //     run_output(create_sine_440());
+    //     std::thread::sleep(std::time::Duration::from_secs(5));
+    run_keyboard();
 }
 
 /// This function determines the sample format, which depends on your system,
@@ -36,14 +154,76 @@ fn run_output(audio_graph: Box<dyn AudioUnit64>) {
         .expect("failed to find a default output device");
     let config = device.default_output_config().unwrap();
     match config.sample_format() {
-        SampleFormat::F32 => run_synth::<f32>(audio_graph, device, config.into()),
-        SampleFormat::I16 => run_synth::<i16>(audio_graph, device, config.into()),
-        SampleFormat::U16 => run_synth::<u16>(audio_graph, device, config.into()),
+        SampleFormat::F32 => run_synth::<f32>(audio_graph, device, config.into(), None),
+        SampleFormat::I16 => run_synth::<i16>(audio_graph, device, config.into(), None),
+        SampleFormat::U16 => run_synth::<u16>(audio_graph, device, config.into(), None),
 
         _ => panic!("Unsupported format"),
     }
 }
 
+/// Like [`run_output`], but in addition to playing the stream live this records
+/// it to `path` as a WAV file. The `WavSpec` is derived from the device's
+/// `StreamConfig` so the file matches whatever format the device hands us.
+///
+/// The returned [`RecordHandle`] must be kept alive for the duration of the
+/// recording; when you are done, `finalize()` the writer so the WAV header is
+/// written out correctly, e.g.
+///
+/// ```no_run
+/// let recorder = run_output_with_recording(create_sine_440(), "output.wav");
+/// std::thread::sleep(std::time::Duration::from_secs(5));
+/// recorder.lock().unwrap().take().unwrap().writer.finalize().unwrap();
+/// ```
+fn run_output_with_recording(audio_graph: Box<dyn AudioUnit64>, path: &str) -> RecordHandle {
+    let host = cpal::default_host();
+    let device = host
+        .default_output_device()
+        .expect("failed to find a default output device");
+    let config = device.default_output_config().unwrap();
+
+    let spec = wav_spec_from_config(&config);
+    let writer = hound::WavWriter::create(path, spec).expect("failed to create WAV file");
+    let recorder: RecordHandle = Arc::new(Mutex::new(Some(Recorder {
+        writer,
+        is_float: spec.sample_format == hound::SampleFormat::Float,
+    })));
+
+    let sample_format = config.sample_format();
+    let stream_config: StreamConfig = config.into();
+    match sample_format {
+        SampleFormat::F32 => {
+            run_synth::<f32>(audio_graph, device, stream_config, Some(recorder.clone()))
+        }
+        SampleFormat::I16 => {
+            run_synth::<i16>(audio_graph, device, stream_config, Some(recorder.clone()))
+        }
+        SampleFormat::U16 => {
+            run_synth::<u16>(audio_graph, device, stream_config, Some(recorder.clone()))
+        }
+
+        _ => panic!("Unsupported format"),
+    }
+
+    recorder
+}
+
+/// Build a `hound::WavSpec` that matches the cpal output config: same channel
+/// count and sample rate, bit depth taken from the sample size, and Int vs
+/// Float chosen from the sample format.
+fn wav_spec_from_config(config: &SupportedStreamConfig) -> hound::WavSpec {
+    hound::WavSpec {
+        channels: config.channels(),
+        sample_rate: config.sample_rate().0,
+        bits_per_sample: (config.sample_format().sample_size() * 8) as u16,
+        sample_format: if config.sample_format().is_float() {
+            hound::SampleFormat::Float
+        } else {
+            hound::SampleFormat::Int
+        },
+    }
+}
+
 /// This function takes an audio graph as an input, along with some the audio
 /// device and config, and starts a thread that will play the audio. The thread
 /// will loop infinitely until the programme exits.
@@ -51,23 +231,61 @@ fn run_synth<T: SizedSample + FromSample<f64>>(
     mut audio_graph: Box<dyn AudioUnit64>,
     device: Device,
     config: StreamConfig,
+    recorder: Option<RecordHandle>,
 ) {
+    let sample_rate = config.sample_rate.0 as f64;
+
+    // The ring buffer decouples generation from playback: the producer thread
+    // fills it with stereo frames and the audio callback only ever drains it,
+    // so a heavy graph can't stall the real-time callback.
+    let buffer = Arc::new(CircularBuffer::<(f64, f64)>::new(RING_CAPACITY));
+
+    // Producer thread: compute audio as fast as the buffer will take it, and —
+    // when recording — write each frame to disk here rather than in the
+    // real-time callback.
+    let producer = buffer.clone();
     std::thread::spawn(move || {
-        let sample_rate = config.sample_rate.0 as f64;
         audio_graph.set_sample_rate(sample_rate);
+        let report_interval = sample_rate as u64;
+        let mut frames = 0u64;
+        let mut reported_underruns = 0;
+        loop {
+            let frame = audio_graph.get_stereo();
+            if let Some(recorder) = &recorder {
+                if let Ok(mut guard) = recorder.lock() {
+                    if let Some(recorder) = guard.as_mut() {
+                        recorder.write_frame(frame.0, frame.1);
+                    }
+                }
+            }
+            // Keep the freshly computed frame and wait for room rather than
+            // dropping it when the consumer is momentarily behind.
+            while !producer.insert(frame) {
+                std::thread::sleep(Duration::from_micros(100));
+            }
 
-        // This is a function that is used to get the next audio sample. It is
-        // written using the closure syntax, so looks a bit different from
-        // normal function definition.
-        let mut next_value = move || audio_graph.get_stereo();
+            // Roughly once a second, surface any new underruns the consumer has
+            // reported so dropouts from a too-heavy graph don't pass silently.
+            frames += 1;
+            if frames % report_interval == 0 {
+                let underruns = producer.underruns();
+                if underruns > reported_underruns {
+                    eprintln!("audio buffer underruns: {underruns}");
+                    reported_underruns = underruns;
+                }
+            }
+        }
+    });
 
+    // Consumer thread: own the cpal stream and drain the buffer in the callback.
+    std::thread::spawn(move || {
         let channels = config.channels as usize;
         let err_fn = |err| eprintln!("an error occurred on stream: {err}");
         let stream = device
             .build_output_stream(
                 &config,
                 move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
-                    write_data(data, channels, &mut next_value)
+                    write_data(data, channels, &buffer)
                 },
                 err_fn,
                 None,
@@ -76,7 +294,7 @@ fn run_synth<T: SizedSample + FromSample<f64>>(
 
         stream.play().unwrap();
         loop {
-            std::thread::sleep(std::time::Duration::from_millis(1));
+            std::thread::sleep(Duration::from_millis(1));
         }
     });
 }
@@ -86,10 +304,10 @@ fn run_synth<T: SizedSample + FromSample<f64>>(
 fn write_data<T: SizedSample + FromSample<f64>>(
     output: &mut [T],
     channels: usize,
-    next_sample: &mut dyn FnMut() -> (f64, f64),
+    buffer: &CircularBuffer<(f64, f64)>,
 ) {
     for frame in output.chunks_mut(channels) {
-        let sample = next_sample();
+        let sample = buffer.drain();
         let left: T = T::from_sample(sample.0);
         let right: T = T::from_sample(sample.1);
 
@@ -99,6 +317,186 @@ fn write_data<T: SizedSample + FromSample<f64>>(
     }
 }
 
+/// Turn the example into a tiny playable instrument.
+///
+/// Unlike the `create_*` functions, which bake every frequency into the graph
+/// before playback starts, this builds the graph around a `shared()` frequency
+/// value and retunes it live. A clone of the `Shared` handle stays on the main
+/// thread, and each recognised key press calls `set_value` to move the
+/// oscillator to a new pitch — demonstrating fundsp's atomic parameter control.
+///
+/// Call this from `main` instead of `run_output` + `sleep`; the key loop keeps
+/// the programme (and therefore the audio thread) alive.
+fn run_keyboard() {
+    // The frequency is stored in a `Shared`, read every sample by `var`.
+    let pitch = shared(261.626);
+    let audio_graph = Box::new(var(&pitch) >> sine());
+    run_output(audio_graph);
+
+    // Keep a clone of the control handle on this thread and drive it from the
+    // keyboard. The home row `a s d f g h j k` maps to a C-major scale.
+    keyboard_loop(pitch);
+}
+
+/// Read keys from stdin and retune `pitch` to the matching note. The loop ends
+/// when stdin is closed (Ctrl-D).
+///
+/// Note: stdin is in the terminal's default line-buffered mode, so keys are
+/// only delivered after you press Enter — type a run of notes (e.g. `adf`) and
+/// hit Enter to hear them retune in sequence. Enabling raw/no-echo terminal
+/// mode (via a crate such as `crossterm`) would make it respond to each
+/// keystroke immediately.
+fn keyboard_loop(pitch: Shared<f64>) {
+    use std::io::Read;
+
+    // `a s d f g h j k` → C4 D4 E4 F4 G4 A4 B4 C5, a C-major scale.
+    let scale = [
+        ('a', 261.626),
+        ('s', 293.665),
+        ('d', 329.628),
+        ('f', 349.228),
+        ('g', 391.995),
+        ('h', 440.000),
+        ('j', 493.883),
+        ('k', 523.251),
+    ];
+
+    println!(
+        "Type notes on the home row a s d f g h j k then press Enter to change \
+         the pitch (Ctrl-D to quit)."
+    );
+    for byte in std::io::stdin().bytes() {
+        let key = match byte {
+            Ok(byte) => byte as char,
+            Err(_) => break,
+        };
+        if let Some((_, freq)) = scale.iter().find(|(k, _)| *k == key) {
+            pitch.set_value(*freq);
+        }
+    }
+}
+
+/// Print an ASCII magnitude-response plot of `unit` to stdout — dB on the
+/// vertical axis, log frequency on the horizontal — so you can *see* what a
+/// `bell_hz`/filter chain does before you hear it.
+///
+/// Probe frequencies are spaced logarithmically between `min_hz` and `max_hz`.
+/// Where fundsp can answer an analytic complex `response` query we use it;
+/// otherwise we fall back to driving the unit with a steady sine and measuring
+/// the steady-state output RMS relative to the input.
+///
+/// `unit` must be a filter: a single-input, single-output graph such as a
+/// `bell_hz`/`lowpass_hz` chain. The RMS fallback feeds the unit one input
+/// sample per frame, so passing a generator (0 inputs) or a multi-channel graph
+/// is rejected up front rather than panicking inside `tick`.
+fn display_response(unit: &mut dyn AudioUnit64, min_hz: f64, max_hz: f64) {
+    if unit.inputs() != 1 || unit.outputs() != 1 {
+        eprintln!(
+            "display_response expects a 1-in/1-out filter, got {} in / {} out",
+            unit.inputs(),
+            unit.outputs()
+        );
+        return;
+    }
+
+    // Plot area: +20 dB at the top down to -40 dB at the bottom.
+    const WIDTH: usize = 61;
+    const HEIGHT: usize = 31;
+    const TOP_DB: f64 = 20.0;
+    const BOTTOM_DB: f64 = -40.0;
+
+    let ratio = (max_hz / min_hz).ln();
+    let mut grid = vec![vec![' '; WIDTH]; HEIGHT];
+
+    for col in 0..WIDTH {
+        // Logarithmic sweep from min_hz to max_hz.
+        let t = col as f64 / (WIDTH - 1) as f64;
+        let freq = min_hz * (t * ratio).exp();
+
+        let gain = match unit.response(0, freq) {
+            Some(response) => response.norm(),
+            None => response_rms(unit, freq),
+        };
+        let db = 20.0 * gain.max(1.0e-9).log10();
+
+        // Quantise dB to a row, clamping to the plotted range.
+        let db = db.clamp(BOTTOM_DB, TOP_DB);
+        let row = ((TOP_DB - db) / (TOP_DB - BOTTOM_DB) * (HEIGHT - 1) as f64).round() as usize;
+        grid[row][col] = '*';
+    }
+
+    // Draw the grid with a dB label every 10 dB on the left margin.
+    for (row, line) in grid.iter().enumerate() {
+        let db = TOP_DB - row as f64 * (TOP_DB - BOTTOM_DB) / (HEIGHT - 1) as f64;
+        let label = if db.rem_euclid(10.0) < 1.0e-6 {
+            format!("{db:>5.0} ")
+        } else {
+            "      ".to_string()
+        };
+        let plotted: String = line.iter().collect();
+        println!("{label}|{plotted}");
+    }
+
+    // Frequency axis: mark each decade boundary that falls inside the sweep.
+    let mut axis = vec![' '; WIDTH];
+    let mut labels = vec![' '; WIDTH];
+    let mut decade = 10f64.powf(min_hz.log10().ceil());
+    while decade <= max_hz {
+        if decade >= min_hz {
+            let col = ((decade / min_hz).ln() / ratio * (WIDTH - 1) as f64).round() as usize;
+            if col < WIDTH {
+                axis[col] = '^';
+                for (i, ch) in format!("{decade:.0}").chars().enumerate() {
+                    if col + i < WIDTH {
+                        labels[col + i] = ch;
+                    }
+                }
+            }
+        }
+        decade *= 10.0;
+    }
+    let axis: String = axis.iter().collect();
+    let labels: String = labels.iter().collect();
+    println!("      +{}", "-".repeat(WIDTH));
+    println!("       {axis}");
+    println!("       {labels}  Hz");
+}
+
+/// Estimate the magnitude response of `unit` at `freq` by driving it with a
+/// unit-amplitude sine and comparing output to input RMS once the unit has
+/// settled. Used when an analytic `response` query is unavailable.
+fn response_rms(unit: &mut dyn AudioUnit64, freq: f64) -> f64 {
+    use std::f64::consts::TAU;
+
+    let sample_rate = 44_100.0;
+    unit.reset();
+    unit.set_sample_rate(sample_rate);
+
+    // Run at least a few cycles to reach steady state, then measure over the
+    // same span.
+    let cycle = (sample_rate / freq).ceil() as usize;
+    let warmup = cycle * 4;
+    let measure = cycle * 4;
+
+    let mut input_energy = 0.0;
+    let mut output_energy = 0.0;
+    for i in 0..(warmup + measure) {
+        let x = (TAU * freq * i as f64 / sample_rate).sin();
+        let mut output = [0.0];
+        unit.tick(&[x], &mut output);
+        if i >= warmup {
+            input_energy += x * x;
+            output_energy += output[0] * output[0];
+        }
+    }
+
+    if input_energy == 0.0 {
+        0.0
+    } else {
+        (output_energy / input_energy).sqrt()
+    }
+}
+
 // ------------------------------------------------------------------
 // You can use any of the functions in this section to make the audio
 // graph. Just replace the function call in `main` at the top.
@@ -121,6 +519,51 @@ fn create_c_major() -> Box<dyn AudioUnit64> {
     Box::new(synth)
 }
 
+/// Inharmonic multiplier set that gives a bell / metallic timbre. Used by
+/// [`create_bell`] as a sensible default for [`create_additive`].
+const BELL_PARTIALS: [(f64, f64); 9] = [
+    (0.8, 1.0),
+    (1.0, 1.0),
+    (1.2, 0.8),
+    (1.7, 0.6),
+    (2.9, 0.5),
+    (4.5, 0.4),
+    (8.8, 0.3),
+    (1.9, 0.6),
+    (3.6, 0.4),
+];
+
+/// Additive synthesiser: sum a sine oscillator per partial, each at
+/// `fundamental_hz * multiplier` and scaled by its weight. The weights are
+/// normalised so the summed output keeps unity gain and does not clip, which
+/// means callers can dial in relative partial strengths without worrying about
+/// the overall level.
+///
+/// This generalises the hard-coded three-note [`create_c_major`]: pass any list
+/// of `(multiplier, weight)` pairs to explore a timbre instead of a chord.
+fn create_additive(fundamental_hz: f64, partials: &[(f64, f64)]) -> Box<dyn AudioUnit64> {
+    use fundsp::hacker::{zero, Net64};
+
+    let total: f64 = partials.iter().map(|(_, weight)| weight).sum();
+    let total = if total == 0.0 { 1.0 } else { total };
+
+    // Start from silence and add each weighted partial in turn, building the
+    // graph dynamically so the partial count is not fixed at compile time.
+    let mut net = Net64::wrap(Box::new(zero()));
+    for (multiplier, weight) in partials {
+        let partial = sine_hz(fundamental_hz * multiplier) * (weight / total);
+        net = net + Net64::wrap(Box::new(partial));
+    }
+
+    Box::new(net)
+}
+
+/// Bell/metallic tone at `fundamental_hz` using the default inharmonic
+/// [`BELL_PARTIALS`]. A convenience wrapper around [`create_additive`].
+fn create_bell(fundamental_hz: f64) -> Box<dyn AudioUnit64> {
+    create_additive(fundamental_hz, &BELL_PARTIALS)
+}
+
 /// Load and play a sample
 fn create_sample() -> Box<dyn AudioUnit64> {
     let wave =
@@ -143,6 +586,56 @@ fn create_sample_with_reverb() -> Box<dyn AudioUnit64> {
     Box::new(synth)
 }
 
+/// Granular-synthesis texture built on fundsp's `Granular64` generator.
+///
+/// Each grain is a short sine burst whose frequency is chosen by exponential
+/// interpolation between `pitch_low` and `pitch_high` and whose stereo position
+/// is randomised, so overlapping grains smear into an evolving cloud rather
+/// than a fixed chord. `voices` is the number of parallel grain streams traced
+/// along the synthesiser's helix, `grain_length` is each grain's duration and
+/// `grain_spacing` the helix revolution length — both in seconds.
+fn create_granular(
+    channels: usize,
+    voices: usize,
+    grain_length: f64,
+    grain_spacing: f64,
+    pitch_low: f64,
+    pitch_high: f64,
+) -> Box<dyn AudioUnit64> {
+    use fundsp::hacker::{pan, sine_hz, xerp11, Granular64};
+
+    // The generator runs once per grain. `x`, `y` and `z` arrive in -1..1 from
+    // the synthesiser's texture, and `v` is the voice position along the helix;
+    // we use them to randomise pitch, amplitude and stereo placement per grain.
+    let generator = move |_t: f64, _b: f64, v: f64, x: f64, y: f64, z: f64| {
+        let freq = xerp11(pitch_low, pitch_high, x);
+        let amp = xerp11(0.02, 0.2, y);
+        let position = (v + z).clamp(-1.0, 1.0);
+        let grain = sine_hz(freq) * amp >> pan(position);
+        // (grain length, envelope length, grain graph) — lengths in seconds.
+        (
+            grain_length,
+            grain_length * 0.5,
+            Box::new(grain) as Box<dyn AudioUnit64>,
+        )
+    };
+
+    // A fixed texture seed keeps the cloud reproducible between runs; the helix
+    // radii and jitter use fundsp's documented defaults.
+    let granular = Granular64::new(
+        channels,
+        voices,
+        grain_spacing,
+        8,
+        0,
+        0.1,
+        0.2,
+        0.01,
+        generator,
+    );
+    Box::new(granular)
+}
+
 // Simple FM synthesiser taken from the FunDSP docs
 fn create_simple_fm() -> Box<dyn AudioUnit64> {
     // Frequency